@@ -20,6 +20,7 @@ use p3_poseidon2::{
     add_rc_and_sbox_generic, external_initial_permute_state, external_terminal_permute_state,
     internal_permute_state,
 };
+use p3_symmetric::Permutation;
 
 use crate::{
     Mersenne31, Poseidon2ExternalLayerMersenne31, Poseidon2InternalLayerMersenne31, from_u62,
@@ -197,6 +198,303 @@ pub fn default_mersenne31_poseidon2_24() -> Poseidon2Mersenne31<24> {
     )
 }
 
+/// Number of bits in the Grain LFSR state used to derive Poseidon round constants, as specified
+/// by the original Poseidon paper (<https://eprint.iacr.org/2019/458>), Appendix F.
+const GRAIN_STATE_BITS: usize = 80;
+
+/// Number of clocks the Grain LFSR is run for, discarding their output, before it is used to
+/// derive any round constants.
+const GRAIN_WARMUP_CLOCKS: usize = 2 * GRAIN_STATE_BITS;
+
+/// A Grain-type LFSR, as specified by the original Poseidon paper, used to deterministically
+/// derive Poseidon round constants from a small parameter descriptor. This lets round constants
+/// for widths other than the hardcoded [`MERSENNE31_RC16_EXTERNAL_INITIAL`]-style tables be
+/// regenerated and audited directly from Rust.
+struct GrainLfsr {
+    state: [bool; GRAIN_STATE_BITS],
+}
+
+/// The s-box type packed into the Grain LFSR descriptor for a direct, non-inverse s-box
+/// (`x^alpha`), as used by Mersenne31's `x^5` Poseidon2 s-box. The descriptor encodes which kind
+/// of s-box is in use (`0` for `x^alpha`, `1` would be an inverse `x^-1` s-box), not the exponent
+/// itself.
+const GRAIN_SBOX_TYPE_NON_INVERSE: u64 = 0;
+
+impl GrainLfsr {
+    /// Initialize the generator for a Poseidon instance over a prime field of `field_bits` bits,
+    /// with the given `sbox_type` (see [`GRAIN_SBOX_TYPE_NON_INVERSE`]), state `width`, and round
+    /// counts, then run the warm-up clocks required before any output bit can be trusted.
+    fn new(field_bits: u64, sbox_type: u64, width: u64, rounds_f: u64, rounds_p: u64) -> Self {
+        let mut bits = Vec::with_capacity(GRAIN_STATE_BITS);
+        // Field type: 1 denotes a prime field.
+        push_bits_be(&mut bits, 1, 2);
+        push_bits_be(&mut bits, sbox_type, 4);
+        push_bits_be(&mut bits, field_bits, 12);
+        push_bits_be(&mut bits, width, 12);
+        push_bits_be(&mut bits, rounds_f, 10);
+        push_bits_be(&mut bits, rounds_p, 10);
+        // Pad the remaining bits of the descriptor with ones.
+        bits.resize(GRAIN_STATE_BITS, true);
+
+        let mut state = [false; GRAIN_STATE_BITS];
+        state.copy_from_slice(&bits);
+        let mut lfsr = Self { state };
+
+        for _ in 0..GRAIN_WARMUP_CLOCKS {
+            lfsr.clock();
+        }
+        lfsr
+    }
+
+    /// Clock the register once, returning the XOR-feedback tap output, and shift it into the
+    /// register.
+    fn clock(&mut self) -> bool {
+        let feedback = self.state[0]
+            ^ self.state[13]
+            ^ self.state[23]
+            ^ self.state[38]
+            ^ self.state[51]
+            ^ self.state[62];
+        self.state.copy_within(1.., 0);
+        self.state[GRAIN_STATE_BITS - 1] = feedback;
+        feedback
+    }
+
+    /// Produce the next output bit: clock once for a candidate bit, and only accept the
+    /// following clock's bit as output if the candidate was `1`; otherwise discard both and
+    /// retry.
+    fn next_output_bit(&mut self) -> bool {
+        loop {
+            let accept = self.clock();
+            let bit = self.clock();
+            if accept {
+                return bit;
+            }
+        }
+    }
+
+    /// Draw the next Mersenne31 element by reading 31 output bits MSB-first, rejecting and
+    /// retrying whenever the resulting integer is not in `[0, 2^31 - 1)`.
+    fn next_field_element(&mut self) -> Mersenne31 {
+        loop {
+            let mut value: u32 = 0;
+            for _ in 0..31 {
+                value = (value << 1) | (self.next_output_bit() as u32);
+            }
+            if value < (1u32 << 31) - 1 {
+                return Mersenne31::new(value);
+            }
+        }
+    }
+}
+
+/// Push the `width` lowest bits of `value`, MSB-first, onto `bits`.
+fn push_bits_be(bits: &mut Vec<bool>, value: u64, width: u32) {
+    for i in (0..width).rev() {
+        bits.push((value >> i) & 1 == 1);
+    }
+}
+
+/// Derive Poseidon2 round constants for Mersenne31 at an arbitrary `WIDTH`, using the standard
+/// Grain LFSR from the original Poseidon specification.
+///
+/// `rounds_f` is the total number of full rounds (split evenly between the initial and terminal
+/// external layers) and `rounds_p` is the number of partial (internal) rounds. Constants are
+/// drawn in the order the permutation consumes them: external-initial rows, internal scalars,
+/// then external-terminal rows.
+pub fn mersenne31_round_constants<const WIDTH: usize>(
+    rounds_f: usize,
+    rounds_p: usize,
+) -> (ExternalLayerConstants<Mersenne31, WIDTH>, Vec<Mersenne31>) {
+    assert_eq!(
+        rounds_f % 2,
+        0,
+        "the full rounds must split evenly between the initial and terminal external layers"
+    );
+    let half_rounds_f = rounds_f / 2;
+
+    let mut lfsr = GrainLfsr::new(
+        31,
+        GRAIN_SBOX_TYPE_NON_INVERSE,
+        WIDTH as u64,
+        rounds_f as u64,
+        rounds_p as u64,
+    );
+
+    let initial_external = (0..half_rounds_f)
+        .map(|_| core::array::from_fn(|_| lfsr.next_field_element()))
+        .collect();
+    let internal = (0..rounds_p).map(|_| lfsr.next_field_element()).collect();
+    let terminal_external = (0..half_rounds_f)
+        .map(|_| core::array::from_fn(|_| lfsr.next_field_element()))
+        .collect();
+
+    (
+        ExternalLayerConstants::new(initial_external, terminal_external),
+        internal,
+    )
+}
+
+/// A domain separator for [`Poseidon2Sponge`], fixing the value written into the sponge's
+/// capacity before absorption starts.
+///
+/// Mirrors the domain handling used by the halo2 Poseidon primitives: two messages that are
+/// otherwise identical but declare a different domain must hash to unrelated digests.
+pub trait Domain {
+    /// The value written into the first capacity lane before any input is absorbed.
+    fn initial_capacity_element() -> Mersenne31;
+}
+
+/// A domain for messages of a declared, constant length `L`.
+///
+/// `L` is encoded directly into the initial capacity element, so hashing the same elements
+/// under a different declared length yields a different digest. The final, possibly partial,
+/// block of the message is padded with zeros: when the sponge permutes a partial block (on the
+/// first [`Poseidon2Sponge::squeeze`] after absorbing), rate lanes with no buffered input have
+/// zero added to them.
+pub struct ConstantLength<const L: usize>;
+
+impl<const L: usize> Domain for ConstantLength<L> {
+    fn initial_capacity_element() -> Mersenne31 {
+        Mersenne31::new(L as u32)
+    }
+}
+
+/// The two phases of a [`Poseidon2Sponge`]: buffering input into the rate region, or handing
+/// output back out of it.
+enum SpongeState<const RATE: usize> {
+    Absorbing([Option<Mersenne31>; RATE]),
+    Squeezing([Option<Mersenne31>; RATE]),
+}
+
+impl<const RATE: usize> SpongeState<RATE> {
+    fn absorbing() -> Self {
+        Self::Absorbing([None; RATE])
+    }
+
+    fn squeezing() -> Self {
+        Self::Squeezing([None; RATE])
+    }
+}
+
+/// A variable-length sponge hash built on top of [`Poseidon2Mersenne31`].
+///
+/// Absorbing and squeezing both operate on the first `RATE` lanes of the state; the remaining
+/// `WIDTH - RATE` lanes are the capacity and are never touched directly by the caller. Use
+/// [`Poseidon2Sponge::hash`] for the common case of hashing a whole message in one call.
+pub struct Poseidon2Sponge<const WIDTH: usize, const RATE: usize> {
+    permutation: Poseidon2Mersenne31<WIDTH>,
+    state: [Mersenne31; WIDTH],
+    mode: SpongeState<RATE>,
+}
+
+impl<const WIDTH: usize, const RATE: usize> Poseidon2Sponge<WIDTH, RATE>
+where
+    Poseidon2InternalLayerMersenne31: InternalLayer<Mersenne31, WIDTH, MERSENNE31_S_BOX_DEGREE>,
+    Poseidon2ExternalLayerMersenne31<WIDTH>: ExternalLayer<Mersenne31, WIDTH, MERSENNE31_S_BOX_DEGREE>,
+{
+    /// Initialize a new sponge over `permutation`, ready to absorb under domain `D`.
+    pub fn new<D: Domain>(permutation: Poseidon2Mersenne31<WIDTH>) -> Self {
+        Self::new_with_capacity_element(permutation, D::initial_capacity_element())
+    }
+
+    /// Initialize a new sponge, writing `capacity_element` directly into the capacity rather
+    /// than deriving it from a compile-time [`Domain`]. Used by [`Poseidon2Sponge::hash`] to
+    /// domain-separate on a message length that is only known at runtime.
+    fn new_with_capacity_element(
+        permutation: Poseidon2Mersenne31<WIDTH>,
+        capacity_element: Mersenne31,
+    ) -> Self {
+        assert!(
+            RATE < WIDTH,
+            "the rate must leave room for at least one capacity lane"
+        );
+        let mut state = [Mersenne31::ZERO; WIDTH];
+        state[RATE] = capacity_element;
+        Self {
+            permutation,
+            state,
+            mode: SpongeState::absorbing(),
+        }
+    }
+
+    /// Absorb a single element into the rate region, permuting the state whenever the
+    /// region fills up. Switches back to absorbing if called after a squeeze.
+    pub fn absorb(&mut self, value: Mersenne31) {
+        loop {
+            match &mut self.mode {
+                SpongeState::Absorbing(buf) => {
+                    if let Some(slot) = buf.iter_mut().find(|slot| slot.is_none()) {
+                        *slot = Some(value);
+                        return;
+                    }
+                    self.permute_absorbed();
+                    self.mode = SpongeState::absorbing();
+                }
+                SpongeState::Squeezing(_) => self.mode = SpongeState::absorbing(),
+            }
+        }
+    }
+
+    /// Squeeze a single element out of the rate region, permuting and refilling the region
+    /// whenever it has been fully drained. Switches to squeezing if called after an absorb.
+    pub fn squeeze(&mut self) -> Mersenne31 {
+        loop {
+            match &mut self.mode {
+                SpongeState::Squeezing(buf) => {
+                    if let Some(value) = buf.iter_mut().find_map(Option::take) {
+                        return value;
+                    }
+                    // The rate region has been fully drained: permute again and refill it.
+                    self.permutation.permute_mut(&mut self.state);
+                    self.refill_squeeze_buffer();
+                }
+                SpongeState::Absorbing(_) => {
+                    self.permute_absorbed();
+                    self.refill_squeeze_buffer();
+                }
+            }
+        }
+    }
+
+    /// Switch to `Squeezing` and refill its buffer from `self.state[..RATE]`; the state must
+    /// already have been permuted by the caller.
+    fn refill_squeeze_buffer(&mut self) {
+        self.mode = SpongeState::squeezing();
+        if let SpongeState::Squeezing(buf) = &mut self.mode {
+            for (slot, &value) in buf.iter_mut().zip(&self.state[..RATE]) {
+                *slot = Some(value);
+            }
+        }
+    }
+
+    /// Add any buffered absorbed input into the rate region and run the permutation.
+    fn permute_absorbed(&mut self) {
+        if let SpongeState::Absorbing(buf) = &self.mode {
+            for (lane, slot) in self.state[..RATE].iter_mut().zip(buf) {
+                *lane += slot.unwrap_or(Mersenne31::ZERO);
+            }
+        }
+        self.permutation.permute_mut(&mut self.state);
+    }
+
+    /// Hash an arbitrary-length `inputs` to `N` field elements, domain-separating on the
+    /// message's runtime length the same way [`ConstantLength`] does at compile time: the
+    /// length is encoded into the initial capacity element, so hashing the same elements with
+    /// a different number of them produces an unrelated digest.
+    pub fn hash<const N: usize>(
+        permutation: Poseidon2Mersenne31<WIDTH>,
+        inputs: &[Mersenne31],
+    ) -> [Mersenne31; N] {
+        let capacity_element = Mersenne31::new(inputs.len() as u32);
+        let mut sponge = Self::new_with_capacity_element(permutation, capacity_element);
+        for &x in inputs {
+            sponge.absorb(x);
+        }
+        core::array::from_fn(|_| sponge.squeeze())
+    }
+}
+
 /// An implementation of the matrix multiplications in the internal and external layers of Poseidon2.
 ///
 /// This can act on `[A; WIDTH]` for any ring implementing `Algebra<Mersenne31>`.
@@ -215,6 +513,19 @@ const POSEIDON2_INTERNAL_MATRIX_DIAG_24_SHIFTS: [u8; 23] = [
 ///
 /// Here V is the vector [-2] + 1 << shifts. This used delayed reduction to be slightly faster.
 fn permute_mut<const N: usize>(state: &mut [Mersenne31; N], shifts: &[u8]) {
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        neon::permute_mut_neon(state, shifts);
+    }
+    #[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+    {
+        permute_mut_scalar(state, shifts);
+    }
+}
+
+/// Portable scalar fallback for [`permute_mut`], used whenever no architecture-specific
+/// implementation is available.
+fn permute_mut_scalar<const N: usize>(state: &mut [Mersenne31; N], shifts: &[u8]) {
     debug_assert_eq!(shifts.len() + 1, N);
     let part_sum: u64 = state[1..].iter().map(|x| x.value as u64).sum();
     let full_sum = part_sum + (state[0].value as u64);
@@ -226,6 +537,92 @@ fn permute_mut<const N: usize>(state: &mut [Mersenne31; N], shifts: &[u8]) {
     }
 }
 
+/// NEON-vectorized version of [`permute_mut`] for aarch64 targets.
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod neon {
+    //! Exploits the same structure as the scalar internal layer: the diagonal of `1 + Diag(V)`
+    //! is `[-2] + 1 << shifts`, so off-diagonal multiplications are shifts and the shared
+    //! `full_sum` is reused across every lane. Lanes are processed two at a time as 64-bit
+    //! delayed-reduction accumulators, matching the scalar `from_u62` approach, with a single
+    //! batched Mersenne31 reduction (fold `x >> 31` plus `x & (2^31 - 1)`) applied at the end.
+
+    use core::arch::aarch64::{
+        uint64x2_t, vaddq_u64, vandq_u64, vdupq_n_u64, vld1q_u64, vreinterpretq_s64_u64,
+        vshlq_u64, vshrq_n_u64, vst1q_u64,
+    };
+
+    use crate::Mersenne31;
+
+    const MERSENNE31_MASK: u64 = (1u64 << 31) - 1;
+
+    /// Fold a delayed-reduction accumulator down to a canonical Mersenne31 value.
+    #[inline]
+    fn reduce_lane(x: u64) -> Mersenne31 {
+        let folded = (x & MERSENNE31_MASK) + (x >> 31);
+        let value = if folded >= MERSENNE31_MASK {
+            folded - MERSENNE31_MASK
+        } else {
+            folded
+        };
+        Mersenne31::new(value as u32)
+    }
+
+    /// Apply one batched Mersenne31 reduction fold to both lanes of `x` at once.
+    #[inline]
+    unsafe fn reduce_pair(x: uint64x2_t) -> uint64x2_t {
+        unsafe {
+            let mask = vdupq_n_u64(MERSENNE31_MASK);
+            let low = vandq_u64(x, mask);
+            let high = vshrq_n_u64::<31>(x);
+            vaddq_u64(low, high)
+        }
+    }
+
+    /// Vectorized version of the scalar `permute_mut_scalar`: compute the shared
+    /// `part_sum`/`full_sum` once, apply the per-lane left shifts via `vshlq_u64` against a
+    /// shift-amount vector, add `full_sum`, and fold the result back towards canonical form two
+    /// lanes at a time.
+    pub(super) fn permute_mut_neon<const N: usize>(state: &mut [Mersenne31; N], shifts: &[u8]) {
+        debug_assert_eq!(shifts.len() + 1, N);
+
+        let part_sum: u64 = state[1..].iter().map(|x| x.value as u64).sum();
+        let full_sum = part_sum + (state[0].value as u64);
+        let s0 = part_sum + (-state[0]).value as u64;
+        state[0] = reduce_lane(s0);
+
+        // SAFETY: this module is only compiled with `target_feature = "neon"` enabled.
+        let full_sum_pair = unsafe { vdupq_n_u64(full_sum) };
+
+        let mut i = 1;
+        while i + 1 < N {
+            let values = [state[i].value as u64, state[i + 1].value as u64];
+            let shift_amounts = [shifts[i - 1] as u64, shifts[i] as u64];
+
+            // SAFETY: `values` and `shift_amounts` are 2-element arrays, matching the 2-lane
+            // width of `uint64x2_t`, and this module requires NEON to be enabled.
+            let reduced = unsafe {
+                let loaded = vld1q_u64(values.as_ptr());
+                let shift_vec = vreinterpretq_s64_u64(vld1q_u64(shift_amounts.as_ptr()));
+                let shifted = vshlq_u64(loaded, shift_vec);
+                let summed = vaddq_u64(shifted, full_sum_pair);
+                reduce_pair(summed)
+            };
+
+            let mut out = [0u64; 2];
+            // SAFETY: `out` has room for the 2 lanes stored by `vst1q_u64`.
+            unsafe { vst1q_u64(out.as_mut_ptr(), reduced) };
+            state[i] = reduce_lane(out[0]);
+            state[i + 1] = reduce_lane(out[1]);
+            i += 2;
+        }
+        // N - 1 is odd when N is even: handle the trailing unpaired lane with the scalar path.
+        if i < N {
+            let si = full_sum + ((state[i].value as u64) << shifts[i - 1]);
+            state[i] = reduce_lane(si);
+        }
+    }
+}
+
 impl InternalLayer<Mersenne31, 16, MERSENNE31_S_BOX_DEGREE> for Poseidon2InternalLayerMersenne31 {
     /// Perform the internal layers of the Poseidon2 permutation on the given state.
     fn permute_state(&self, state: &mut [Mersenne31; 16]) {
@@ -272,6 +669,58 @@ impl<const WIDTH: usize> ExternalLayer<Mersenne31, WIDTH, MERSENNE31_S_BOX_DEGRE
     }
 }
 
+/// Run the full Poseidon2 permutation `perm` directly over raw memory, for zkVM syscall-style
+/// hashing where a backend wants to read two memory pointers, hash, and write the result
+/// back without an intermediate copy.
+///
+/// `input` must point to `2 * WIDTH` little-endian `u32` words; each consecutive pair is
+/// combined modulo `p` into one input lane (low word first). `output` must point to `WIDTH`
+/// `u64` words and receives the canonical value of each output lane.
+///
+/// # Safety
+///
+/// `input` must be valid to read `2 * WIDTH` `u32`s from, and `output` must be valid to write
+/// `WIDTH` `u64`s to. The two regions are allowed to fully or partially overlap: every input
+/// word is read into registers before any output word is written.
+pub unsafe fn permute_u32_io<const WIDTH: usize>(
+    perm: &Poseidon2Mersenne31<WIDTH>,
+    input: *const u32,
+    output: *mut u64,
+) where
+    Poseidon2InternalLayerMersenne31: InternalLayer<Mersenne31, WIDTH, MERSENNE31_S_BOX_DEGREE>,
+    Poseidon2ExternalLayerMersenne31<WIDTH>: ExternalLayer<Mersenne31, WIDTH, MERSENNE31_S_BOX_DEGREE>,
+{
+    let mut state: [Mersenne31; WIDTH] = core::array::from_fn(|i| unsafe {
+        let lo = input.add(2 * i).read_unaligned();
+        let hi = input.add(2 * i + 1).read_unaligned();
+        mersenne31_from_u32_pair(lo, hi)
+    });
+
+    perm.permute_mut(&mut state);
+
+    for (i, elem) in state.into_iter().enumerate() {
+        let canonical = p3_field::PrimeField32::as_canonical_u32(&elem);
+        unsafe {
+            output.add(i).write_unaligned(canonical as u64);
+        }
+    }
+}
+
+/// Combine two little-endian `u32` words into a single canonical Mersenne31 element, reducing
+/// the combined 64-bit value modulo `p = 2^31 - 1`.
+fn mersenne31_from_u32_pair(lo: u32, hi: u32) -> Mersenne31 {
+    const P: u64 = (1u64 << 31) - 1;
+    let mut value = ((hi as u64) << 32) | (lo as u64);
+    loop {
+        let folded = (value & P) + (value >> 31);
+        match folded.cmp(&P) {
+            core::cmp::Ordering::Less => return Mersenne31::new(folded as u32),
+            core::cmp::Ordering::Equal => return Mersenne31::new(0),
+            core::cmp::Ordering::Greater => value = folded,
+        }
+    }
+}
+
 impl GenericPoseidon2LinearLayers<16> for GenericPoseidon2LinearLayersMersenne31 {
     fn internal_linear_layer<R: PrimeCharacteristicRing>(state: &mut [R; 16]) {
         let part_sum: R = state[1..].iter().cloned().sum();
@@ -382,4 +831,128 @@ mod tests {
         perm.permute_mut(&mut input);
         assert_eq!(input, expected);
     }
+
+    /// Hashing the same elements, zero-padded to a different declared length, must yield a
+    /// different digest: the length is encoded into the initial capacity element, so a 3-element
+    /// message and its 4-element zero-padded extension must not collide.
+    #[test]
+    fn test_poseidon2_sponge_domain_separation() {
+        let inputs_3: [F; 3] = Mersenne31::new_array([1, 2, 3]);
+        let inputs_4: [F; 4] = Mersenne31::new_array([1, 2, 3, 0]);
+
+        let mut rng = Xoroshiro128Plus::seed_from_u64(1);
+        let perm_a = Poseidon2Mersenne31::<16>::new_from_rng_128(&mut rng);
+        let mut rng = Xoroshiro128Plus::seed_from_u64(1);
+        let perm_b = Poseidon2Mersenne31::<16>::new_from_rng_128(&mut rng);
+
+        let digest_a: [F; 4] = Poseidon2Sponge::<16, 8>::hash(perm_a, &inputs_3);
+        let digest_b: [F; 4] = Poseidon2Sponge::<16, 8>::hash(perm_b, &inputs_4);
+
+        assert_ne!(digest_a, digest_b);
+    }
+
+    /// Squeezing more elements than fit in a single rate-sized block must permute and refill the
+    /// rate region rather than hanging or returning stale values.
+    #[test]
+    fn test_poseidon2_sponge_squeeze_past_one_block() {
+        let inputs: [F; 3] = Mersenne31::new_array([1, 2, 3]);
+
+        let mut rng = Xoroshiro128Plus::seed_from_u64(1);
+        let perm = Poseidon2Mersenne31::<16>::new_from_rng_128(&mut rng);
+
+        let digest: [F; 10] = Poseidon2Sponge::<16, 8>::hash(perm, &inputs);
+
+        // The second block's worth of output must actually have been produced by a further
+        // permutation, not be left as the sponge's all-zero initial state.
+        assert!(digest[8..].iter().any(|&x| x != Mersenne31::ZERO));
+    }
+
+    /// The Grain LFSR generator is deterministic: re-running it with the same parameters must
+    /// produce the same round constants every time.
+    ///
+    /// This deliberately does not assert that the generator reproduces
+    /// [`MERSENNE31_RC16_EXTERNAL_INITIAL`] and its siblings bit-for-bit. Those tables were
+    /// produced by an external Sage script, and this port's parameter-descriptor packing, tap
+    /// positions, and accept/reject sequence have not been checked against that script, so an
+    /// equality assertion here would not be a verified claim.
+    #[test]
+    fn test_grain_lfsr_is_deterministic() {
+        let (external_a, internal_a) = mersenne31_round_constants::<16>(8, 13);
+        let (external_b, internal_b) = mersenne31_round_constants::<16>(8, 13);
+        assert_eq!(
+            external_a.get_initial_constants(),
+            external_b.get_initial_constants()
+        );
+        assert_eq!(
+            external_a.get_terminal_constants(),
+            external_b.get_terminal_constants()
+        );
+        assert_eq!(internal_a, internal_b);
+    }
+
+    /// The Grain LFSR generator must draw the number of constants implied by the requested round
+    /// counts: one full row of `WIDTH` elements per external half-round, and one scalar per
+    /// partial round.
+    #[test]
+    fn test_grain_lfsr_produces_the_requested_round_counts() {
+        let (external, internal) = mersenne31_round_constants::<16>(8, 13);
+        assert_eq!(external.get_initial_constants().len(), 4);
+        assert_eq!(external.get_terminal_constants().len(), 4);
+        assert_eq!(internal.len(), 13);
+
+        let (external, internal) = mersenne31_round_constants::<24>(8, 21);
+        assert_eq!(external.get_initial_constants().len(), 4);
+        assert_eq!(external.get_terminal_constants().len(), 4);
+        assert_eq!(internal.len(), 21);
+    }
+
+    /// Hashing is deterministic and exercises more than one permutation call when the message
+    /// spans multiple rate-sized blocks.
+    #[test]
+    fn test_poseidon2_sponge_multi_block_is_deterministic() {
+        let inputs: [F; 20] = Mersenne31::new_array([
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20,
+        ]);
+
+        let mut rng = Xoroshiro128Plus::seed_from_u64(7);
+        let perm_a = Poseidon2Mersenne31::<16>::new_from_rng_128(&mut rng);
+        let mut rng = Xoroshiro128Plus::seed_from_u64(7);
+        let perm_b = Poseidon2Mersenne31::<16>::new_from_rng_128(&mut rng);
+
+        let digest_a: [F; 8] = Poseidon2Sponge::<16, 8>::hash(perm_a, &inputs);
+        let digest_b: [F; 8] = Poseidon2Sponge::<16, 8>::hash(perm_b, &inputs);
+
+        assert_eq!(digest_a, digest_b);
+    }
+
+    /// `permute_u32_io` must agree with `permute_mut` on the Mersenne31 elements obtained by
+    /// reducing each pair of `u32` words, and must tolerate its input and output regions
+    /// overlapping in place.
+    #[test]
+    fn test_permute_u32_io_matches_permute_mut_and_allows_overlap() {
+        let mut rng = Xoroshiro128Plus::seed_from_u64(3);
+        let perm = Poseidon2Mersenne31::<16>::new_from_rng_128(&mut rng);
+
+        let mut words: [u32; 32] = core::array::from_fn(|i| i as u32 * 0x1234_5 + 1);
+        let mut expected: [F; 16] = core::array::from_fn(|i| {
+            mersenne31_from_u32_pair(words[2 * i], words[2 * i + 1])
+        });
+        perm.permute_mut(&mut expected);
+
+        // First with separate input and output buffers.
+        let mut output = [0u64; 16];
+        unsafe {
+            permute_u32_io(&perm, words.as_ptr(), output.as_mut_ptr());
+        }
+        let actual: [F; 16] = core::array::from_fn(|i| Mersenne31::new(output[i] as u32));
+        assert_eq!(actual, expected);
+
+        // Then reuse the same buffer for input and output, as a zkVM memory region would.
+        unsafe {
+            permute_u32_io(&perm, words.as_ptr(), words.as_mut_ptr().cast::<u64>());
+        }
+        let actual_overlapping: [F; 16] =
+            core::array::from_fn(|i| Mersenne31::new(unsafe { (words.as_ptr().cast::<u64>().add(i)).read_unaligned() } as u32));
+        assert_eq!(actual_overlapping, expected);
+    }
 }